@@ -0,0 +1,109 @@
+use std::path::PathBuf;
+
+use axum::{body::Body, http::StatusCode, response::IntoResponse};
+use futures_util::TryStreamExt;
+use tokio_util::io::StreamReader;
+use uuid::Uuid;
+
+use crate::storage::{sanitize_file_name, UPLOADS_DIR};
+
+/// `POST /upload/archive` — streams a `.tar.gz` request body to a temp file,
+/// then unpacks it into `uploads/` on a blocking task, skipping any entry that
+/// would escape the uploads directory. Lets a client push many files in one
+/// request instead of one multipart POST per file.
+///
+/// Archive entries are flattened to their basename: this crate keeps a flat
+/// `uploads/` namespace (see [`crate::storage`]), so an entry like
+/// `photos/trip/1.jpg` is written as `1.jpg`, not a nested directory.
+pub async fn upload_archive(body: Body) -> impl IntoResponse {
+    tracing::info!("POST /upload/archive");
+
+    let tmp_path = PathBuf::from(UPLOADS_DIR).join(format!(".tmp-{}.tar.gz", Uuid::new_v4()));
+    let mut tmp_file = match tokio::fs::File::create(&tmp_path).await {
+        Ok(file) => file,
+        Err(err) => {
+            tracing::warn!("failed to create temp file for archive upload: {err}");
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let stream = body
+        .into_data_stream()
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err));
+    let mut reader = StreamReader::new(stream);
+    if let Err(err) = tokio::io::copy(&mut reader, &mut tmp_file).await {
+        tracing::warn!("failed writing archive upload: {err}");
+        let _ = tokio::fs::remove_file(&tmp_path).await;
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    drop(tmp_file);
+
+    let result = tokio::task::spawn_blocking(move || extract_archive(&tmp_path)).await;
+    let written = match result {
+        Ok(Ok(written)) => written,
+        Ok(Err(err)) => {
+            tracing::warn!("failed to extract archive: {err}");
+            return Err(StatusCode::BAD_REQUEST);
+        }
+        Err(err) => {
+            tracing::warn!("archive extraction task panicked: {err}");
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    Ok(axum::Json(written))
+}
+
+/// Unpacks `archive_path` (a `.tar.gz` file) into [`UPLOADS_DIR`], validating
+/// every entry before writing it, and always removes the archive file
+/// afterwards. Returns the list of paths written, relative to `uploads/`.
+fn extract_archive(archive_path: &std::path::Path) -> std::io::Result<Vec<String>> {
+    let result = (|| {
+        let file = std::fs::File::open(archive_path)?;
+        let decoder = flate2::read::GzDecoder::new(file);
+        let mut archive = tar::Archive::new(decoder);
+
+        let uploads_root = std::path::Path::new(UPLOADS_DIR).canonicalize()?;
+        let mut written = Vec::new();
+
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let entry_path = entry.path()?.into_owned();
+            let entry_type = entry.header().entry_type();
+
+            // Entries are never trusted: reject symlinks and hard links
+            // outright, and skip directory entries since the uploads
+            // namespace is flat.
+            if entry_type.is_symlink() || entry_type.is_hard_link() {
+                tracing::warn!("skipping unsafe archive entry: {}", entry_path.display());
+                continue;
+            }
+            if entry_type.is_dir() {
+                continue;
+            }
+
+            // Flatten to the basename rather than honoring directory
+            // components, matching the crate-wide flat `uploads/` namespace
+            // so every extracted file stays visible to `/list`, `/download`,
+            // and the share-link subsystem.
+            let Some(file_name) = entry_path.to_str().and_then(sanitize_file_name) else {
+                tracing::warn!("skipping unsafe archive entry: {}", entry_path.display());
+                continue;
+            };
+
+            let target = uploads_root.join(&file_name);
+            if !target.starts_with(&uploads_root) {
+                tracing::warn!("skipping archive entry escaping uploads dir: {}", entry_path.display());
+                continue;
+            }
+
+            entry.unpack(&target)?;
+            written.push(file_name);
+        }
+
+        Ok(written)
+    })();
+
+    let _ = std::fs::remove_file(archive_path);
+    result
+}
@@ -0,0 +1,71 @@
+use std::collections::HashSet;
+
+use axum::{
+    extract::Request,
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+/// The set of API keys accepted by [`require_api_key`], loaded once at startup.
+#[derive(Clone, Debug)]
+pub struct ApiKeys(HashSet<String>);
+
+impl ApiKeys {
+    /// Reads `API_KEY` (a single key) and/or `API_KEYS` (a comma-separated list)
+    /// from the environment. Returns `None` if neither is set, meaning auth is
+    /// disabled.
+    pub fn from_env() -> Option<Self> {
+        let mut keys = HashSet::new();
+
+        if let Ok(key) = std::env::var("API_KEY") {
+            if !key.is_empty() {
+                keys.insert(key);
+            }
+        }
+
+        if let Ok(list) = std::env::var("API_KEYS") {
+            keys.extend(
+                list.split(',')
+                    .map(str::trim)
+                    .filter(|key| !key.is_empty())
+                    .map(str::to_string),
+            );
+        }
+
+        if keys.is_empty() {
+            None
+        } else {
+            Some(Self(keys))
+        }
+    }
+
+    fn contains(&self, key: &str) -> bool {
+        self.0.contains(key)
+    }
+}
+
+/// Middleware that requires a valid `Authorization: Bearer <key>` header,
+/// rejecting the request with `401` otherwise. Routes are opted into this with
+/// `axum::middleware::from_fn_with_state`, so an operator can choose which
+/// routes are public and which are protected by how the router is built in
+/// `main`.
+pub async fn require_api_key(
+    axum::extract::State(keys): axum::extract::State<ApiKeys>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match provided {
+        Some(token) if keys.contains(token) => next.run(request).await,
+        _ => {
+            tracing::warn!("rejected request with missing or invalid bearer token");
+            StatusCode::UNAUTHORIZED.into_response()
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use axum::{
+    body::Body,
+    extract::Query,
+    http::{header, HeaderValue, StatusCode},
+    response::IntoResponse,
+};
+use tokio_util::io::ReaderStream;
+
+use crate::storage::resolve_upload_path;
+
+/// How much the producer task can get ahead of the response body before it
+/// blocks, so a slow client backpressures the ZIP writer instead of letting
+/// it buffer an unbounded amount of compressed data in memory.
+const PIPE_BUFFER_BYTES: usize = 64 * 1024;
+
+/// `GET /download/bundle?filename=a&filename=b` — streams the requested
+/// uploads into a single ZIP, compressing and writing to the response body as
+/// entries are read so neither the whole archive nor the whole body is
+/// staged in memory.
+pub async fn download_bundle(Query(params): Query<Vec<(String, String)>>) -> impl IntoResponse {
+    tracing::info!("GET /download/bundle");
+
+    let filenames: Vec<String> = params
+        .into_iter()
+        .filter(|(key, _)| key == "filename")
+        .map(|(_, value)| value)
+        .collect();
+
+    if filenames.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let (writer_half, reader_half) = tokio::io::duplex(PIPE_BUFFER_BYTES);
+
+    tokio::spawn(async move {
+        if let Err(err) = write_bundle(filenames, writer_half).await {
+            tracing::warn!("failed writing zip bundle: {err}");
+        }
+    });
+
+    let mut headers = axum::http::HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/zip"));
+    headers.insert(
+        header::CONTENT_DISPOSITION,
+        HeaderValue::from_static("attachment; filename=bundle.zip"),
+    );
+
+    let body = Body::from_stream(ReaderStream::new(reader_half));
+    Ok((headers, body))
+}
+
+/// Compresses each requested file into `writer` as a ZIP, skipping any name
+/// that fails the usual path-safety validation or does not exist. Each file
+/// is copied straight from disk into its ZIP entry rather than buffered
+/// whole, so a single large file in the bundle can't blow up memory.
+async fn write_bundle(
+    filenames: Vec<String>,
+    writer: impl tokio::io::AsyncWrite + Unpin,
+) -> std::io::Result<()> {
+    use async_zip::{tokio::write::ZipFileWriter, Compression, ZipEntryBuilder};
+
+    let mut zip = ZipFileWriter::with_tokio(writer);
+
+    for filename in filenames {
+        let Some(upload_path) = resolve_upload_path(&filename) else {
+            tracing::warn!("skipping unsafe bundle entry: {filename}");
+            continue;
+        };
+
+        let mut file = match tokio::fs::File::open(&upload_path).await {
+            Ok(file) => file,
+            Err(err) => {
+                tracing::warn!("skipping missing bundle entry `{filename}`: {err}");
+                continue;
+            }
+        };
+
+        // Use the sanitized basename `resolve_upload_path` resolved to as the
+        // entry name, never the raw query value, so a crafted `filename`
+        // can't smuggle path components into the ZIP (zip-slip on extract).
+        let Some(entry_name) = upload_path.file_name().and_then(|name| name.to_str()) else {
+            tracing::warn!("skipping bundle entry with no basename: {filename}");
+            continue;
+        };
+
+        let entry = ZipEntryBuilder::new(entry_name.to_string().into(), Compression::Deflate);
+        let mut entry_writer = zip
+            .write_entry_stream(entry)
+            .await
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+        tokio::io::copy(&mut file, &mut entry_writer).await?;
+        entry_writer
+            .close()
+            .await
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+    }
+
+    zip.close()
+        .await
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+    Ok(())
+}
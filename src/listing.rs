@@ -0,0 +1,81 @@
+use std::time::UNIX_EPOCH;
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::share::AppState;
+use crate::storage::UPLOADS_DIR;
+
+/// Metadata about a single uploaded file, as returned by `GET /list`.
+#[derive(Serialize)]
+pub struct FileMeta {
+    name: String,
+    size: u64,
+    modified: u64,
+    /// Whether an active share link currently points at this file.
+    shared: bool,
+}
+
+#[derive(Deserialize, Default)]
+pub struct ListQuery {
+    /// Sort key: `name` (default), `size`, or `mtime`.
+    sort: Option<String>,
+    /// Only include files whose name starts with this prefix.
+    prefix: Option<String>,
+}
+
+/// `GET /list?sort=name|size|mtime&prefix=...` — lists uploaded files with
+/// size, modification time, and whether they currently have an active share
+/// link.
+pub async fn list_upload(
+    State(state): State<AppState>,
+    Query(query): Query<ListQuery>,
+) -> impl IntoResponse {
+    tracing::info!("GET /list");
+
+    let shared = state.shared_filenames();
+
+    let entries = match std::fs::read_dir(UPLOADS_DIR) {
+        Ok(entries) => entries,
+        Err(_) => return Json(Vec::<FileMeta>::new()),
+    };
+
+    let mut files: Vec<FileMeta> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            if name.starts_with(".tmp-") {
+                return None;
+            }
+            let metadata = entry.metadata().ok()?;
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_secs())
+                .unwrap_or(0);
+            Some(FileMeta {
+                shared: shared.contains(&name),
+                name,
+                size: metadata.len(),
+                modified,
+            })
+        })
+        .filter(|file| match &query.prefix {
+            Some(prefix) => file.name.starts_with(prefix.as_str()),
+            None => true,
+        })
+        .collect();
+
+    match query.sort.as_deref() {
+        Some("size") => files.sort_by_key(|file| file.size),
+        Some("mtime") => files.sort_by_key(|file| file.modified),
+        _ => files.sort_by(|a, b| a.name.cmp(&b.name)),
+    }
+
+    Json(files)
+}
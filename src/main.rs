@@ -1,30 +1,57 @@
-use std::{
-    collections::HashMap,
-    fs::{self, File},
-    io::Write,
-};
+mod archive;
+mod auth;
+mod bundle;
+mod listing;
+mod share;
+mod storage;
+
+use std::{collections::HashMap, fs};
 
+use archive::upload_archive;
+use auth::{require_api_key, ApiKeys};
 use axum::{
-    extract::{Multipart, Query},
+    extract::{DefaultBodyLimit, Multipart, Query},
     http::{self, StatusCode},
+    middleware,
     response::IntoResponse,
     routing::{get, post},
     Router,
 };
+use bundle::download_bundle;
+use futures_util::TryStreamExt;
+use listing::list_upload;
+use share::{create_share, download_share, AppState};
+use storage::resolve_upload_path;
+use tokio_util::io::StreamReader;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-#[tokio::main(flavor = "current_thread")]
-async fn main() {
-    // initializing step
-    fs::create_dir_all("uploads").unwrap();
+/// Default cap on upload size when `MAX_UPLOAD_BYTES` is not set: 1 GiB.
+const DEFAULT_MAX_UPLOAD_BYTES: usize = 1024 * 1024 * 1024;
 
-    let app = Router::new()
-        .route("/", get(health_check))
-        .route("/upload", post(upload))
-        .route("/list", get(list_upload))
-        .route("/download", get(download))
-        .fallback(handler_404);
+/// Which routes require a bearer token, controlled by the `AUTH_MODE` env var.
+/// Defaults to `write-protected` so uploads need a key but downloads/listing
+/// stay public, mirroring how the deploy endpoint is gated.
+enum AuthMode {
+    /// No auth at all; every route is public.
+    Open,
+    /// `/upload` requires a key; `/download` and `/list` stay public.
+    WriteProtected,
+    /// Every route except the health check requires a key.
+    LockedDown,
+}
 
+impl AuthMode {
+    fn from_env() -> Self {
+        match std::env::var("AUTH_MODE").as_deref() {
+            Ok("open") => Self::Open,
+            Ok("locked-down") => Self::LockedDown,
+            _ => Self::WriteProtected,
+        }
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
     // logging
     tracing_subscriber::registry()
         .with(
@@ -34,6 +61,77 @@ async fn main() {
         .with(tracing_subscriber::fmt::layer().with_target(false))
         .init();
 
+    // initializing step
+    fs::create_dir_all("uploads").unwrap();
+
+    let keys = ApiKeys::from_env();
+    let mode = AuthMode::from_env();
+
+    if keys.is_none() && !matches!(mode, AuthMode::Open) {
+        tracing::error!(
+            "AUTH_MODE requires API_KEY/API_KEYS to be set, but no keys were found; refusing to start unauthenticated"
+        );
+        std::process::exit(1);
+    }
+
+    let share_state = AppState::new();
+    share::spawn_sweeper(share_state.clone());
+
+    let share_link_route = Router::new()
+        .route("/s/:token", get(download_share))
+        .with_state(share_state.clone());
+    let list_route = Router::new()
+        .route("/list", get(list_upload))
+        .with_state(share_state.clone());
+    let create_share_route = Router::new()
+        .route("/share", post(create_share))
+        .with_state(share_state);
+
+    let app = match (keys, mode) {
+        (Some(keys), AuthMode::WriteProtected) => Router::new()
+            .route("/", get(health_check))
+            .route("/download", get(download))
+            .route("/download/bundle", get(download_bundle))
+            .merge(share_link_route)
+            .merge(list_route)
+            .merge(
+                Router::new()
+                    .route("/upload", post(upload))
+                    .route("/upload/archive", post(upload_archive))
+                    .merge(create_share_route)
+                    .route_layer(middleware::from_fn_with_state(keys, require_api_key)),
+            ),
+        (Some(keys), AuthMode::LockedDown) => Router::new()
+            .route("/", get(health_check))
+            .merge(share_link_route)
+            .merge(
+                Router::new()
+                    .route("/upload", post(upload))
+                    .route("/upload/archive", post(upload_archive))
+                    .route("/download", get(download))
+                    .route("/download/bundle", get(download_bundle))
+                    .merge(list_route)
+                    .merge(create_share_route)
+                    .route_layer(middleware::from_fn_with_state(keys, require_api_key)),
+            ),
+        _ => Router::new()
+            .route("/", get(health_check))
+            .route("/upload", post(upload))
+            .route("/upload/archive", post(upload_archive))
+            .route("/download", get(download))
+            .route("/download/bundle", get(download_bundle))
+            .merge(share_link_route)
+            .merge(list_route)
+            .merge(create_share_route),
+    }
+    .fallback(handler_404);
+
+    let max_upload_bytes: usize = std::env::var("MAX_UPLOAD_BYTES")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_MAX_UPLOAD_BYTES);
+    let app = app.layer(DefaultBodyLimit::max(max_upload_bytes));
+
     let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
     tracing::debug!("listening on {}", listener.local_addr().unwrap());
     axum::serve(listener, app).await.unwrap();
@@ -45,21 +143,6 @@ async fn health_check() -> impl IntoResponse {
     StatusCode::OK
 }
 
-// list uploaded files
-async fn list_upload() -> impl IntoResponse {
-    tracing::info!("GET /upload");
-    let files: Vec<String> = match fs::read_dir("uploads") {
-        Ok(files) => files
-            .filter_map(Result::ok)
-            .filter_map(|entry| entry.file_name().into_string().ok())
-            .collect(),
-        _ => {
-            return axum::Json(Vec::new());
-        }
-    };
-    axum::Json(files)
-}
-
 // 404 handler
 async fn handler_404() -> impl IntoResponse {
     tracing::info!("404 Not Found");
@@ -73,41 +156,66 @@ async fn download(query: Query<HashMap<String, String>>) -> impl IntoResponse {
         Some(filename) => filename,
         _ => return Err(StatusCode::BAD_REQUEST),
     };
-    let upload_path = format!("uploads/{}", filename);
-    let body = match fs::read(upload_path) {
+    let upload_path = match resolve_upload_path(filename) {
+        Some(upload_path) => upload_path,
+        None => return Err(StatusCode::BAD_REQUEST),
+    };
+    let body = match fs::read(&upload_path) {
         Ok(body) => body,
         _ => return Err(StatusCode::NOT_FOUND),
     };
-    // set header
+    // Build the header from the sanitized basename, never the raw query
+    // value, so control characters can't break the header or smuggle a
+    // different name into it.
+    let sanitized_name = upload_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("download");
     let mut headers = http::HeaderMap::new();
-    headers.insert(
-        http::header::CONTENT_DISPOSITION,
-        http::HeaderValue::from_str(&format!("attachment; filename={}", filename)).unwrap(),
-    );
+    let header_value =
+        match http::HeaderValue::from_str(&format!("attachment; filename={}", sanitized_name)) {
+            Ok(header_value) => header_value,
+            Err(_) => return Err(StatusCode::BAD_REQUEST),
+        };
+    headers.insert(http::header::CONTENT_DISPOSITION, header_value);
     Ok((headers, body))
 }
 
 async fn upload(mut multipart: Multipart) -> impl IntoResponse {
     tracing::info!("PUT /upload");
-    let field = match multipart.next_field().await.unwrap() {
-        Some(field) => field,
+    let field = match multipart.next_field().await {
+        Ok(Some(field)) => field,
         _ => return Err(StatusCode::BAD_REQUEST),
     };
 
-    let name = field.name().unwrap().to_string();
-    let file_name = field.file_name().unwrap().to_string();
-    let data = field.bytes().await.unwrap();
-    tracing::info!("Length of `{name}` (`{file_name}`) is {} bytes", data.len());
+    let name = field.name().unwrap_or("").to_string();
+    let file_name = match field.file_name() {
+        Some(file_name) => file_name.to_string(),
+        None => return Err(StatusCode::BAD_REQUEST),
+    };
 
-    let upload_path = format!("uploads/{}", file_name);
-    let mut file = match File::create(upload_path) {
+    let upload_path = match resolve_upload_path(&file_name) {
+        Some(upload_path) => upload_path,
+        None => return Err(StatusCode::BAD_REQUEST),
+    };
+    let mut file = match tokio::fs::File::create(&upload_path).await {
         Ok(file) => file,
         _ => return Err(StatusCode::BAD_REQUEST),
     };
 
-    if file.write_all(&data).is_err() || file.flush().is_err() {
-        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    // Stream the field straight to disk instead of buffering it in memory, so
+    // a large (or malicious) upload can't OOM the process.
+    let stream = field.map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err));
+    let mut reader = StreamReader::new(stream);
+    let bytes_written = match tokio::io::copy(&mut reader, &mut file).await {
+        Ok(bytes_written) => bytes_written,
+        Err(err) => {
+            tracing::warn!("failed writing upload `{file_name}`: {err}");
+            let _ = tokio::fs::remove_file(&upload_path).await;
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
     };
+    tracing::info!("Length of `{name}` (`{file_name}`) is {bytes_written} bytes");
 
     Ok(StatusCode::CREATED)
 }
@@ -0,0 +1,239 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::resolve_upload_path;
+
+const TOKEN_ALPHABET: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+const TOKEN_LEN: usize = 10;
+
+/// A registered share link: which file it points at, when it expires, and how
+/// many downloads it has left (if capped).
+#[derive(Clone)]
+pub struct ShareEntry {
+    pub filename: String,
+    pub created_at: SystemTime,
+    pub expires_at: SystemTime,
+    pub max_downloads: Option<u32>,
+    pub downloads: u32,
+    /// Whether the sweeper is allowed to delete `filename` from `uploads/`
+    /// once this entry expires or is exhausted. Opt-in at `create_share`
+    /// time, since a share timing out should never destroy a file the user
+    /// still wants around.
+    pub delete_on_expiry: bool,
+}
+
+impl ShareEntry {
+    fn is_expired(&self) -> bool {
+        SystemTime::now() >= self.expires_at
+    }
+
+    fn is_exhausted(&self) -> bool {
+        matches!(self.max_downloads, Some(max) if self.downloads >= max)
+    }
+}
+
+/// Shared app state, cloned into every handler that needs access to the
+/// in-memory share link table.
+#[derive(Clone)]
+pub struct AppState {
+    shares: Arc<Mutex<HashMap<String, ShareEntry>>>,
+    rng: Arc<Mutex<SmallRng>>,
+}
+
+impl AppState {
+    pub fn new() -> Self {
+        Self {
+            shares: Arc::new(Mutex::new(HashMap::new())),
+            rng: Arc::new(Mutex::new(SmallRng::from_entropy())),
+        }
+    }
+
+    fn next_token(&self) -> String {
+        let mut rng = self.rng.lock().unwrap();
+        (0..TOKEN_LEN)
+            .map(|_| TOKEN_ALPHABET[rng.gen_range(0..TOKEN_ALPHABET.len())] as char)
+            .collect()
+    }
+
+    /// Returns the set of filenames currently backing at least one active
+    /// (unexpired, not-exhausted) share link.
+    pub fn shared_filenames(&self) -> std::collections::HashSet<String> {
+        self.shares
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|entry| !entry.is_expired() && !entry.is_exhausted())
+            .map(|entry| entry.filename.clone())
+            .collect()
+    }
+
+    /// Removes every expired or download-exhausted entry, returning the
+    /// filenames of any that opted into `delete_on_expiry` and are no longer
+    /// referenced by a still-live entry (so a timed-out link never deletes a
+    /// file another active share, or the user's independent upload, still
+    /// needs).
+    pub fn sweep_expired(&self) -> Vec<String> {
+        let mut shares = self.shares.lock().unwrap();
+        let mut removed = Vec::new();
+        shares.retain(|_, entry| {
+            let expired = entry.is_expired() || entry.is_exhausted();
+            if expired {
+                removed.push(entry.clone());
+            }
+            !expired
+        });
+
+        let still_referenced: std::collections::HashSet<&str> = shares
+            .values()
+            .map(|entry| entry.filename.as_str())
+            .collect();
+
+        removed
+            .into_iter()
+            .filter(|entry| entry.delete_on_expiry && !still_referenced.contains(entry.filename.as_str()))
+            .map(|entry| entry.filename)
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CreateShareParams {
+    filename: String,
+    /// Lifetime of the link in seconds; defaults to one hour.
+    #[serde(default)]
+    ttl_seconds: Option<u64>,
+    #[serde(default)]
+    max_downloads: Option<u32>,
+    /// Whether the backing file should be deleted from `uploads/` once this
+    /// share expires or is exhausted, as long as no other live share still
+    /// references it. Defaults to `false`.
+    #[serde(default)]
+    delete_on_expiry: bool,
+}
+
+#[derive(Serialize)]
+pub struct CreateShareResponse {
+    token: String,
+    expires_at: u64,
+}
+
+const DEFAULT_TTL_SECONDS: u64 = 60 * 60;
+
+/// `POST /share?filename=...&ttl_seconds=...&max_downloads=...&delete_on_expiry=...`
+/// — registers an uploaded file for sharing and returns a short-lived token.
+pub async fn create_share(
+    State(state): State<AppState>,
+    Query(params): Query<CreateShareParams>,
+) -> impl IntoResponse {
+    tracing::info!("POST /share");
+
+    let Some(upload_path) = resolve_upload_path(&params.filename) else {
+        return Err(StatusCode::BAD_REQUEST);
+    };
+    if !upload_path.exists() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let Some(filename) = upload_path.file_name().and_then(|name| name.to_str()) else {
+        return Err(StatusCode::BAD_REQUEST);
+    };
+
+    let ttl = Duration::from_secs(params.ttl_seconds.unwrap_or(DEFAULT_TTL_SECONDS));
+    let created_at = SystemTime::now();
+    let expires_at = created_at + ttl;
+
+    let token = state.next_token();
+    let entry = ShareEntry {
+        // Store the sanitized basename `resolve_upload_path` resolved to,
+        // not the raw query value, so later re-resolution in
+        // `download_share`/the sweeper stays consistent with what was
+        // validated here.
+        filename: filename.to_string(),
+        created_at,
+        expires_at,
+        max_downloads: params.max_downloads,
+        downloads: 0,
+        delete_on_expiry: params.delete_on_expiry,
+    };
+    state.shares.lock().unwrap().insert(token.clone(), entry);
+
+    let expires_at_unix = expires_at
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    Ok(axum::Json(CreateShareResponse {
+        token,
+        expires_at: expires_at_unix,
+    }))
+}
+
+/// `GET /s/:token` — streams the shared file if the token is unexpired and
+/// under its download cap, decrementing the remaining count.
+pub async fn download_share(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> impl IntoResponse {
+    tracing::info!("GET /s/{token}");
+
+    let upload_path = {
+        let mut shares = state.shares.lock().unwrap();
+        let Some(entry) = shares.get_mut(&token) else {
+            return Err(StatusCode::NOT_FOUND);
+        };
+
+        if entry.is_expired() || entry.is_exhausted() {
+            shares.remove(&token);
+            return Err(StatusCode::GONE);
+        }
+
+        entry.downloads += 1;
+        resolve_upload_path(&entry.filename)
+    };
+    let Some(upload_path) = upload_path else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+
+    let body = match tokio::fs::read(&upload_path).await {
+        Ok(body) => body,
+        Err(_) => return Err(StatusCode::NOT_FOUND),
+    };
+
+    let mut headers = axum::http::HeaderMap::new();
+    if let Some(name) = upload_path.file_name().and_then(|n| n.to_str()) {
+        headers.insert(
+            axum::http::header::CONTENT_DISPOSITION,
+            axum::http::HeaderValue::from_str(&format!("attachment; filename={}", name))
+                .unwrap(),
+        );
+    }
+    Ok((headers, body))
+}
+
+/// Spawns the background task that periodically removes expired or
+/// exhausted share entries, deleting their backing files from `uploads/` only
+/// for entries that opted into `delete_on_expiry` and are no longer
+/// referenced by another live share.
+pub fn spawn_sweeper(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            for filename in state.sweep_expired() {
+                tracing::info!("share link expired, removing `{filename}`");
+                if let Some(path) = resolve_upload_path(&filename) {
+                    let _ = tokio::fs::remove_file(path).await;
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,57 @@
+use std::path::{Path, PathBuf};
+
+/// Root directory that all uploads live under. Every handler that maps a
+/// client-supplied name to a path on disk goes through [`resolve_upload_path`]
+/// so the "stays inside `uploads/`" invariant holds crate-wide.
+pub const UPLOADS_DIR: &str = "uploads";
+
+/// Reduces a client-supplied file name to a safe basename: directory
+/// components, leading slashes, null bytes, and control characters are
+/// stripped, and a bare `.`/`..`/empty result is rejected.
+///
+/// Returns `None` when nothing safe is left to use.
+pub fn sanitize_file_name(raw: &str) -> Option<String> {
+    let candidate = Path::new(raw)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("");
+
+    let cleaned: String = candidate
+        .chars()
+        .filter(|c| !c.is_control() && *c != '\0')
+        .collect();
+
+    if cleaned.is_empty() || cleaned == "." || cleaned == ".." {
+        return None;
+    }
+
+    Some(cleaned)
+}
+
+/// Sanitizes `raw` and resolves it to a path inside [`UPLOADS_DIR`], rejecting
+/// it if the sanitized name does not exist inside the uploads directory and
+/// the nearest existing ancestor (e.g. `uploads/` itself) is used to resolve
+/// it, ensuring the final path cannot escape the directory.
+///
+/// Used by every handler that turns a client-supplied name into a filesystem
+/// path.
+pub fn resolve_upload_path(raw: &str) -> Option<PathBuf> {
+    let file_name = sanitize_file_name(raw)?;
+    let candidate = Path::new(UPLOADS_DIR).join(&file_name);
+
+    let uploads_root = Path::new(UPLOADS_DIR).canonicalize().ok()?;
+
+    // The file may not exist yet (e.g. we're about to create it), so
+    // canonicalize what does exist and check the sanitized name wouldn't
+    // escape it; canonicalize the full path when it already exists.
+    let canonical = match candidate.canonicalize() {
+        Ok(canonical) => canonical,
+        Err(_) => uploads_root.join(&file_name),
+    };
+
+    if canonical.starts_with(&uploads_root) {
+        Some(candidate)
+    } else {
+        None
+    }
+}